@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct Cents(u32);
 
 impl Cents {
@@ -21,7 +24,10 @@ impl fmt::Display for Cents {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+/// Highest balance the cash till will hold.
+pub const TILL_CEILING: Cents = Cents(999999);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct Grams(u32);
 
 impl Grams {
@@ -49,33 +55,227 @@ impl fmt::Display for Grams {
     }
 }
 
-#[derive(Debug)]
+/// Maximum number of distinct SKUs a `Store` can carry at once.
+pub const INVENTORY_CAPACITY: usize = 30;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ItemKind {
+    /// Fungible units tracked only by count, e.g. flat washers.
+    Stackable,
+    /// Physically distinct units, each tracked on its own.
+    Individual,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Item {
     pub name: String,
     pub id: u32,
     pub cost: Cents,
     pub weight: Grams,
+    pub kind: ItemKind,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StackedItem {
+    item: Item,
+    qty: u32,
+}
+
+impl StackedItem {
+    pub fn new(item: Item, qty: u32) -> Self {
+        Self { item, qty }
+    }
+
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn qty(&self) -> u32 {
+        self.qty
+    }
+
+    /// Splits `amount` units off the stack, or `None` if more is requested
+    /// than the stack holds.
+    pub fn take(&mut self, amount: u32) -> Option<u32> {
+        if amount > self.qty {
+            return None;
+        }
+        self.qty -= amount;
+        Some(amount)
+    }
+
+    /// Adds `amount` units back onto the stack, e.g. when restocking a
+    /// canceled order.
+    pub fn restock(&mut self, amount: u32) {
+        self.qty += amount;
+    }
+}
+
+/// Unique id issued to a single physical unit of an `Individual` item, so
+/// that the exact unit shipped can be traced through returns and recalls.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct ItemEntityId(u32);
+
+impl ItemEntityId {
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndividualItem {
+    item: Item,
+    entity_ids: Vec<ItemEntityId>,
+}
+
+impl IndividualItem {
+    pub fn new(item: Item, entity_ids: Vec<ItemEntityId>) -> Self {
+        Self { item, entity_ids }
+    }
+
+    pub fn item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn count(&self) -> u32 {
+        self.entity_ids.len() as u32
+    }
+
+    pub fn entity_ids(&self) -> &[ItemEntityId] {
+        &self.entity_ids
+    }
+
+    /// Drains `amount` entity ids off the front, or `None` if fewer remain.
+    pub fn take_entity_ids(&mut self, amount: u32) -> Option<Vec<ItemEntityId>> {
+        if amount as usize > self.entity_ids.len() {
+            return None;
+        }
+        Some(self.entity_ids.drain(..amount as usize).collect())
+    }
+
+    pub fn return_entity_ids(&mut self, ids: Vec<ItemEntityId>) {
+        self.entity_ids.extend(ids);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum InventoryEntry {
+    Stacked(StackedItem),
+    Individual(IndividualItem),
+}
+
+impl InventoryEntry {
+    pub fn item(&self) -> &Item {
+        match self {
+            InventoryEntry::Stacked(s) => s.item(),
+            InventoryEntry::Individual(i) => i.item(),
+        }
+    }
+
+    pub fn qty(&self) -> u32 {
+        match self {
+            InventoryEntry::Stacked(s) => s.qty(),
+            InventoryEntry::Individual(i) => i.count(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OrderLine {
     pub item_id: u32,
     pub qty: u32,
+    /// Entity ids fulfilled for this line, if the item is `Individual`.
+    /// Empty for `Stackable` lines.
+    pub entity_ids: Vec<ItemEntityId>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OrderStatus {
+    New { date_created: String },
+    Shipped { tracking: String },
+    Completed { date_delivered: String },
+    Canceled { reason: String },
+    Returned { reason: String },
+}
+
+impl OrderStatus {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            OrderStatus::New { .. } => "New",
+            OrderStatus::Shipped { .. } => "Shipped",
+            OrderStatus::Completed { .. } => "Completed",
+            OrderStatus::Canceled { .. } => "Canceled",
+            OrderStatus::Returned { .. } => "Returned",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Order {
     pub id: u32,
+    pub status: OrderStatus,
     pub cost: Cents,
     pub ship_weight: Grams,
     pub items: Vec<OrderLine>,
 }
 
+#[derive(Debug, Error)]
+pub enum ShopError {
+    #[error("unknown item id: {0}")]
+    UnknownItem(u32),
+
+    #[error("insufficient stock for item {id}: requested {requested}, available {available}")]
+    InsufficientStock {
+        id: u32,
+        requested: u32,
+        available: u32,
+    },
+
+    #[error("order total does not fit its numeric representation")]
+    OrderTooLarge,
+
+    #[error("inventory full ({capacity} distinct SKUs)")]
+    CapacityFull { capacity: usize },
+
+    #[error("item {0} is individually tracked; use take_entity_ids/return_entity_ids instead")]
+    ItemIsIndividual(u32),
+
+    #[error("item {0} is stackable; use adjust_stock instead")]
+    ItemIsStackable(u32),
+
+    #[error("unknown order id: {0}")]
+    UnknownOrder(u32),
+
+    #[error("order {order_id}: cannot transition from {from} to {to}")]
+    IllegalTransition {
+        order_id: u32,
+        from: &'static str,
+        to: &'static str,
+    },
+
+    #[error("cannot remove ${requested} from till balance of ${balance}")]
+    CashInsufficientBalance { balance: Cents, requested: Cents },
+
+    #[error("adding ${amount} to till balance of ${balance} would exceed ceiling of ${ceiling}")]
+    CashExceedsCeiling {
+        balance: Cents,
+        amount: Cents,
+        ceiling: Cents,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct Store {
-    inventory: HashMap<u32, (Item, u32)>,
+    inventory: HashMap<u32, InventoryEntry>,
     orders: Vec<Order>,
     next_item_id: u32,
     next_order_id: u32,
+    next_entity_id: u32,
+    cash: Cents,
 }
 
 impl Store {
@@ -85,9 +285,55 @@ impl Store {
             orders: Vec::new(),
             next_item_id: 1,
             next_order_id: 1,
+            next_entity_id: 1,
+            cash: Cents::new(0),
         }
     }
 
+    pub fn next_entity_id(&mut self) -> ItemEntityId {
+        let id = self.next_entity_id;
+        self.next_entity_id += 1;
+        ItemEntityId::new(id)
+    }
+
+    pub fn cash_balance(&self) -> Cents {
+        self.cash
+    }
+
+    /// Adds `amount` to the till, rejecting amounts that would push the
+    /// balance past [`TILL_CEILING`].
+    pub fn add_cash(&mut self, amount: Cents) -> Result<Cents, ShopError> {
+        let sum = self
+            .cash
+            .as_u32()
+            .checked_add(amount.as_u32())
+            .filter(|sum| *sum <= TILL_CEILING.as_u32())
+            .ok_or(ShopError::CashExceedsCeiling {
+                balance: self.cash,
+                amount,
+                ceiling: TILL_CEILING,
+            })?;
+
+        self.cash = Cents::new(sum);
+        Ok(self.cash)
+    }
+
+    /// Removes `amount` from the till, rejecting withdrawals larger than the
+    /// current balance.
+    pub fn remove_cash(&mut self, amount: Cents) -> Result<Cents, ShopError> {
+        let diff = self
+            .cash
+            .as_u32()
+            .checked_sub(amount.as_u32())
+            .ok_or(ShopError::CashInsufficientBalance {
+                balance: self.cash,
+                requested: amount,
+            })?;
+
+        self.cash = Cents::new(diff);
+        Ok(self.cash)
+    }
+
     pub fn inventory_len(&self) -> usize {
         self.inventory.len()
     }
@@ -99,64 +345,163 @@ impl Store {
     }
 
     pub fn inventory_get(&self, item_id: u32) -> Option<(&Item, u32)> {
-        self.inventory.get(&item_id).map(|(item, qty)| (item, *qty))
+        self.inventory
+            .get(&item_id)
+            .map(|entry| (entry.item(), entry.qty()))
     }
 
-    pub fn stock(&mut self, item: Item, quantity: u32) {
-        self.inventory.insert(item.id, (item, quantity));
+    /// Returns the entity ids currently in stock for an `Individual` item, so
+    /// the exact units on hand can be traced. `None` if `item_id` is unknown
+    /// or the item is `Stackable`.
+    pub fn inventory_entity_ids(&self, item_id: u32) -> Option<&[ItemEntityId]> {
+        match self.inventory.get(&item_id)? {
+            InventoryEntry::Individual(ind) => Some(ind.entity_ids()),
+            InventoryEntry::Stacked(_) => None,
+        }
     }
 
-    pub fn stock_new(&mut self, name: String, cost: Cents, weight: Grams, quantity: u32) -> u32 {
+    /// Inserts `item` into inventory, replacing any existing entry with the
+    /// same id. Rejects brand-new SKUs once `INVENTORY_CAPACITY` distinct
+    /// SKUs are already stocked.
+    pub fn stock(&mut self, item: Item, quantity: u32) -> Result<(), ShopError> {
+        if !self.inventory.contains_key(&item.id) && self.inventory.len() >= INVENTORY_CAPACITY {
+            return Err(ShopError::CapacityFull {
+                capacity: INVENTORY_CAPACITY,
+            });
+        }
+
+        let entry = match item.kind {
+            ItemKind::Stackable => InventoryEntry::Stacked(StackedItem::new(item, quantity)),
+            ItemKind::Individual => {
+                let entity_ids = (0..quantity).map(|_| self.next_entity_id()).collect();
+                InventoryEntry::Individual(IndividualItem::new(item, entity_ids))
+            }
+        };
+        self.inventory.insert(entry.item().id, entry);
+        Ok(())
+    }
+
+    pub fn stock_new(
+        &mut self,
+        name: String,
+        cost: Cents,
+        weight: Grams,
+        quantity: u32,
+        kind: ItemKind,
+    ) -> Result<u32, ShopError> {
         let id = self.next_item_id;
         let item = Item {
             name,
             id,
             cost,
             weight,
+            kind,
         };
-        self.stock(item, quantity);
+        self.stock(item, quantity)?;
         self.next_item_id += 1;
-        id
+        Ok(id)
     }
 
-    pub fn adjust_stock(&mut self, item_id: u32, qty_change: i32) -> Result<u32, String> {
-        let (_item, qty) = self
+    /// Adjusts a `Stackable` item's count by `qty_change`. `Individual` items
+    /// are tracked per-unit; use [`Store::take_entity_ids`] and
+    /// [`Store::return_entity_ids`] for those instead.
+    pub fn adjust_stock(&mut self, item_id: u32, qty_change: i32) -> Result<u32, ShopError> {
+        let entry = self
             .inventory
             .get_mut(&item_id)
-            .ok_or_else(|| format!("Unknown id: {item_id}"))?;
+            .ok_or(ShopError::UnknownItem(item_id))?;
+
+        let stacked = match entry {
+            InventoryEntry::Stacked(s) => s,
+            InventoryEntry::Individual(_) => return Err(ShopError::ItemIsIndividual(item_id)),
+        };
 
-        let new_qty = (*qty as i64) + (qty_change as i64);
-        if new_qty < 0 {
-            return Err(format!("Not enough stock (ID: {item_id})"));
+        if qty_change < 0 {
+            let requested = (-qty_change) as u32;
+            let available = stacked.qty();
+            stacked
+                .take(requested)
+                .ok_or(ShopError::InsufficientStock {
+                    id: item_id,
+                    requested,
+                    available,
+                })?;
+        } else {
+            stacked.restock(qty_change as u32);
         }
 
-        *qty = new_qty as u32;
-        Ok(*qty)
+        Ok(stacked.qty())
     }
 
-    pub fn commit_order(&mut self, lines: Vec<OrderLine>) -> Order {
+    /// Reserves `amount` entity ids off the front of an `Individual` item's
+    /// stock, for recording on an `OrderLine`.
+    pub fn take_entity_ids(
+        &mut self,
+        item_id: u32,
+        amount: u32,
+    ) -> Result<Vec<ItemEntityId>, ShopError> {
+        match self.inventory.get_mut(&item_id) {
+            Some(InventoryEntry::Individual(ind)) => {
+                let available = ind.count();
+                ind.take_entity_ids(amount)
+                    .ok_or(ShopError::InsufficientStock {
+                        id: item_id,
+                        requested: amount,
+                        available,
+                    })
+            }
+            Some(InventoryEntry::Stacked(_)) => Err(ShopError::ItemIsStackable(item_id)),
+            None => Err(ShopError::UnknownItem(item_id)),
+        }
+    }
+
+    /// Restores previously-taken entity ids to an `Individual` item's stock.
+    pub fn return_entity_ids(
+        &mut self,
+        item_id: u32,
+        ids: Vec<ItemEntityId>,
+    ) -> Result<(), ShopError> {
+        match self.inventory.get_mut(&item_id) {
+            Some(InventoryEntry::Individual(ind)) => {
+                ind.return_entity_ids(ids);
+                Ok(())
+            }
+            Some(InventoryEntry::Stacked(_)) => Err(ShopError::ItemIsStackable(item_id)),
+            None => Err(ShopError::UnknownItem(item_id)),
+        }
+    }
+
+    pub fn commit_order(&mut self, lines: Vec<OrderLine>) -> Result<Order, ShopError> {
         let mut order_cost: u64 = 0;
         let mut order_grams: u64 = 0;
 
         for l in &lines {
-            let (item, _avail) = self.inventory.get(&l.item_id).expect("Line item not found");
+            let item = self
+                .inventory
+                .get(&l.item_id)
+                .ok_or(ShopError::UnknownItem(l.item_id))?
+                .item();
             let qty_u64 = u64::from(l.qty);
             order_cost += u64::from(item.cost.as_u32()) * qty_u64;
             order_grams += u64::from(item.weight.as_u32()) * qty_u64;
         }
 
-        let cost_u32: u32 = order_cost.try_into().expect("order cost too large");
-        let grams_u32: u32 = order_grams.try_into().expect("order weight too large");
+        let cost_u32: u32 = order_cost.try_into().map_err(|_| ShopError::OrderTooLarge)?;
+        let grams_u32: u32 = order_grams.try_into().map_err(|_| ShopError::OrderTooLarge)?;
 
         let new_order = Order {
             id: self.next_order_id,
+            status: OrderStatus::New {
+                date_created: "12DEC2025".to_string(),
+            },
             cost: Cents(cost_u32),
             ship_weight: Grams(grams_u32),
             items: lines,
         };
 
+        self.add_cash(new_order.cost)?;
         self.next_order_id += 1;
-        new_order
+        Ok(new_order)
     }
 
     pub fn orders(&self) -> &[Order] {
@@ -166,4 +511,326 @@ impl Store {
     pub fn push_order(&mut self, order: Order) {
         self.orders.push(order);
     }
+
+    /// Moves an order to `new_status`, validating that the transition is legal
+    /// for the order's current status. Canceling or returning an order restocks
+    /// its line quantities; shipping and completing do not.
+    pub fn transition_order(
+        &mut self,
+        order_id: u32,
+        new_status: OrderStatus,
+    ) -> Result<&Order, ShopError> {
+        let idx = self
+            .orders
+            .iter()
+            .position(|o| o.id == order_id)
+            .ok_or(ShopError::UnknownOrder(order_id))?;
+
+        let restock = match (&self.orders[idx].status, &new_status) {
+            (OrderStatus::New { .. }, OrderStatus::Shipped { .. }) => false,
+            (OrderStatus::New { .. }, OrderStatus::Canceled { .. }) => true,
+            (OrderStatus::Shipped { .. }, OrderStatus::Completed { .. }) => false,
+            (OrderStatus::Shipped { .. }, OrderStatus::Returned { .. }) => true,
+            (current, attempted) => {
+                return Err(ShopError::IllegalTransition {
+                    order_id,
+                    from: current.label(),
+                    to: attempted.label(),
+                })
+            }
+        };
+
+        if restock {
+            let lines: Vec<(u32, u32, Vec<ItemEntityId>)> = self.orders[idx]
+                .items
+                .iter()
+                .map(|l| (l.item_id, l.qty, l.entity_ids.clone()))
+                .collect();
+            for (item_id, qty, entity_ids) in lines {
+                if entity_ids.is_empty() {
+                    let _ = self.adjust_stock(item_id, qty as i32);
+                } else {
+                    let _ = self.return_entity_ids(item_id, entity_ids);
+                }
+            }
+            self.remove_cash(self.orders[idx].cost)?;
+        }
+
+        self.orders[idx].status = new_status;
+        Ok(&self.orders[idx])
+    }
+}
+
+#[derive(Debug)]
+enum StockDelta {
+    Stacked { item_id: u32, qty: u32 },
+    Individual { item_id: u32, entity_ids: Vec<ItemEntityId> },
+}
+
+/// Tracks stock reservations made while building an order so they can all be
+/// undone together. Reserve lines with [`StoreTransaction::reserve_stock`] /
+/// [`StoreTransaction::reserve_entity_ids`], then either [`StoreTransaction::commit`]
+/// to finalize the order or [`StoreTransaction::rollback`] to restore every
+/// reservation made so far.
+pub struct StoreTransaction<'a> {
+    store: &'a mut Store,
+    deltas: Vec<StockDelta>,
+}
+
+impl<'a> StoreTransaction<'a> {
+    pub fn new(store: &'a mut Store) -> Self {
+        Self {
+            store,
+            deltas: Vec::new(),
+        }
+    }
+
+    pub fn store(&self) -> &Store {
+        self.store
+    }
+
+    /// Reserves `qty` of a `Stackable` item, recording the delta for rollback.
+    pub fn reserve_stock(&mut self, item_id: u32, qty: u32) -> Result<(), ShopError> {
+        self.store.adjust_stock(item_id, -(qty as i32))?;
+        self.deltas.push(StockDelta::Stacked { item_id, qty });
+        Ok(())
+    }
+
+    /// Reserves `amount` entity ids of an `Individual` item, recording the
+    /// delta for rollback.
+    pub fn reserve_entity_ids(
+        &mut self,
+        item_id: u32,
+        amount: u32,
+    ) -> Result<Vec<ItemEntityId>, ShopError> {
+        let entity_ids = self.store.take_entity_ids(item_id, amount)?;
+        self.deltas.push(StockDelta::Individual {
+            item_id,
+            entity_ids: entity_ids.clone(),
+        });
+        Ok(entity_ids)
+    }
+
+    /// Finalizes `lines` into a committed `Order`. On success the reserved
+    /// stock stays decremented; on failure the caller should call
+    /// [`StoreTransaction::rollback`] to restore it.
+    pub fn commit(&mut self, lines: Vec<OrderLine>) -> Result<Order, ShopError> {
+        let order = self.store.commit_order(lines)?;
+        self.deltas.clear();
+        Ok(order)
+    }
+
+    /// Undoes every reservation recorded so far, in reverse order, restoring
+    /// each reserved quantity and entity id to the inventory. Restored entity
+    /// ids are appended to the back of the item's list, so their order there
+    /// can differ from the order before the transaction began.
+    pub fn rollback(self) {
+        for delta in self.deltas.into_iter().rev() {
+            match delta {
+                StockDelta::Stacked { item_id, qty } => {
+                    let _ = self.store.adjust_stock(item_id, qty as i32);
+                }
+                StockDelta::Individual {
+                    item_id,
+                    entity_ids,
+                } => {
+                    let _ = self.store.return_entity_ids(item_id, entity_ids);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_item(id: u32, kind: ItemKind) -> Item {
+        Item {
+            name: "widget".to_string(),
+            id,
+            cost: Cents::new(100),
+            weight: Grams::new(50),
+            kind,
+        }
+    }
+
+    #[test]
+    fn ship_then_complete_is_legal() {
+        let mut store = Store::new();
+        store.stock(sample_item(1, ItemKind::Stackable), 5).unwrap();
+
+        let mut txn = StoreTransaction::new(&mut store);
+        txn.reserve_stock(1, 2).unwrap();
+        let order = txn
+            .commit(vec![OrderLine {
+                item_id: 1,
+                qty: 2,
+                entity_ids: Vec::new(),
+            }])
+            .unwrap();
+        store.push_order(order);
+        let order_id = store.orders()[0].id;
+
+        store
+            .transition_order(
+                order_id,
+                OrderStatus::Shipped {
+                    tracking: "TRACK1".to_string(),
+                },
+            )
+            .unwrap();
+        store
+            .transition_order(
+                order_id,
+                OrderStatus::Completed {
+                    date_delivered: "01JAN2026".to_string(),
+                },
+            )
+            .unwrap();
+
+        assert!(matches!(store.orders()[0].status, OrderStatus::Completed { .. }));
+    }
+
+    #[test]
+    fn completing_a_new_order_is_illegal() {
+        let mut store = Store::new();
+        store.stock(sample_item(1, ItemKind::Stackable), 5).unwrap();
+        let order = store
+            .commit_order(vec![OrderLine {
+                item_id: 1,
+                qty: 2,
+                entity_ids: Vec::new(),
+            }])
+            .unwrap();
+        store.push_order(order);
+        let order_id = store.orders()[0].id;
+
+        let err = store
+            .transition_order(
+                order_id,
+                OrderStatus::Completed {
+                    date_delivered: "01JAN2026".to_string(),
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, ShopError::IllegalTransition { .. }));
+    }
+
+    #[test]
+    fn canceling_a_new_order_restocks_it() {
+        let mut store = Store::new();
+        store.stock(sample_item(1, ItemKind::Stackable), 5).unwrap();
+
+        let mut txn = StoreTransaction::new(&mut store);
+        txn.reserve_stock(1, 2).unwrap();
+        let order = txn
+            .commit(vec![OrderLine {
+                item_id: 1,
+                qty: 2,
+                entity_ids: Vec::new(),
+            }])
+            .unwrap();
+        store.push_order(order);
+        let order_id = store.orders()[0].id;
+
+        store
+            .transition_order(
+                order_id,
+                OrderStatus::Canceled {
+                    reason: "customer request".to_string(),
+                },
+            )
+            .unwrap();
+
+        let (_, qty) = store.inventory_get(1).unwrap();
+        assert_eq!(qty, 5);
+    }
+
+    #[test]
+    fn adjust_stock_rejects_over_withdrawal() {
+        let mut store = Store::new();
+        store.stock(sample_item(1, ItemKind::Stackable), 3).unwrap();
+
+        let err = store.adjust_stock(1, -5).unwrap_err();
+        assert!(matches!(err, ShopError::InsufficientStock { .. }));
+
+        let (_, qty) = store.inventory_get(1).unwrap();
+        assert_eq!(qty, 3);
+    }
+
+    #[test]
+    fn take_entity_ids_rejects_over_withdrawal() {
+        let mut store = Store::new();
+        store
+            .stock(sample_item(1, ItemKind::Individual), 2)
+            .unwrap();
+
+        let err = store.take_entity_ids(1, 5).unwrap_err();
+        assert!(matches!(err, ShopError::InsufficientStock { .. }));
+        assert_eq!(store.inventory_entity_ids(1).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn add_cash_rejects_over_ceiling() {
+        let mut store = Store::new();
+        store.add_cash(Cents::new(TILL_CEILING.as_u32() - 10)).unwrap();
+
+        let err = store.add_cash(Cents::new(50)).unwrap_err();
+
+        assert!(matches!(err, ShopError::CashExceedsCeiling { .. }));
+        assert_eq!(store.cash_balance(), Cents::new(TILL_CEILING.as_u32() - 10));
+    }
+
+    #[test]
+    fn remove_cash_rejects_underflow() {
+        let mut store = Store::new();
+        store.add_cash(Cents::new(100)).unwrap();
+
+        let err = store.remove_cash(Cents::new(200)).unwrap_err();
+
+        assert!(matches!(err, ShopError::CashInsufficientBalance { .. }));
+        assert_eq!(store.cash_balance(), Cents::new(100));
+    }
+
+    #[test]
+    fn commit_order_rejects_sale_that_would_exceed_ceiling() {
+        let mut store = Store::new();
+        store
+            .stock(sample_item(1, ItemKind::Stackable), 100)
+            .unwrap();
+        store.add_cash(TILL_CEILING).unwrap();
+
+        let err = store
+            .commit_order(vec![OrderLine {
+                item_id: 1,
+                qty: 1,
+                entity_ids: Vec::new(),
+            }])
+            .unwrap_err();
+
+        assert!(matches!(err, ShopError::CashExceedsCeiling { .. }));
+    }
+
+    #[test]
+    fn rollback_restores_reservations_on_failed_commit() {
+        let mut store = Store::new();
+        store.stock(sample_item(1, ItemKind::Stackable), 5).unwrap();
+
+        let mut txn = StoreTransaction::new(&mut store);
+        txn.reserve_stock(1, 3).unwrap();
+
+        let err = txn
+            .commit(vec![OrderLine {
+                item_id: 99,
+                qty: 1,
+                entity_ids: Vec::new(),
+            }])
+            .unwrap_err();
+        assert!(matches!(err, ShopError::UnknownItem(99)));
+        txn.rollback();
+
+        let (_, qty) = store.inventory_get(1).unwrap();
+        assert_eq!(qty, 5);
+    }
 }
@@ -0,0 +1,35 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::domain::Store;
+
+/// Persists and restores a [`Store`]'s full state, so inventory and order
+/// history survive between runs instead of being re-seeded each time.
+pub trait StoreGateway {
+    fn save(&self, store: &Store) -> io::Result<()>;
+    fn load(&self) -> io::Result<Store>;
+}
+
+/// A [`StoreGateway`] backed by a single JSON file on disk.
+pub struct JsonFileGateway {
+    path: PathBuf,
+}
+
+impl JsonFileGateway {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl StoreGateway for JsonFileGateway {
+    fn save(&self, store: &Store) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(store).map_err(io::Error::other)?;
+        fs::write(&self.path, json)
+    }
+
+    fn load(&self) -> io::Result<Store> {
+        let json = fs::read_to_string(&self.path)?;
+        serde_json::from_str(&json).map_err(io::Error::other)
+    }
+}
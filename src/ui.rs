@@ -1,8 +1,11 @@
 use std::io::{self, Write};
 
-use crate::domain::{Cents, Grams, Order, OrderLine, Store};
+use crate::domain::{
+    Cents, Grams, ItemEntityId, ItemKind, Order, OrderLine, OrderStatus, ShopError, Store,
+    StoreTransaction, INVENTORY_CAPACITY,
+};
 
-fn read_str(prompt: &str) -> io::Result<String> {
+pub(crate) fn read_str(prompt: &str) -> io::Result<String> {
     print!("{prompt}");
     io::stdout().flush()?;
 
@@ -29,19 +32,43 @@ fn retry_read_u32(prompt: &str) -> io::Result<u32> {
     }
 }
 
+/// Prints a tailored message for `ShopError::InsufficientStock` and
+/// `ShopError::CapacityFull`; every other variant is printed with its
+/// `Display` text.
+fn print_shop_error(e: &ShopError) {
+    match e {
+        ShopError::InsufficientStock {
+            id,
+            requested,
+            available,
+        } => eprintln!("Only {available} of item {id} available (requested {requested})."),
+        ShopError::CapacityFull { capacity } => {
+            eprintln!("Inventory is full ({capacity} distinct SKUs); stock an existing item or make room first.")
+        }
+        _ => eprintln!("{e}"),
+    }
+}
+
 pub fn create_stock(store: &mut Store) -> io::Result<()> {
     println!("Creating new stock item...");
     let input_name = read_str("  Item name: ")?;
     let input_cents = retry_read_u32("  Item price (cents): ")?;
     let input_grams = retry_read_u32("  Item weight (g): ")?;
     let input_qty = retry_read_u32("  Quantity: ")?;
+    let input_kind = match read_str("  Stackable (fungible) item? (y/n): ")?.as_str() {
+        "y" | "Y" => ItemKind::Stackable,
+        _ => ItemKind::Individual,
+    };
 
-    store.stock_new(
+    if let Err(e) = store.stock_new(
         input_name,
         Cents::new(input_cents),
         Grams::new(input_grams),
         input_qty,
-    );
+        input_kind,
+    ) {
+        print_shop_error(&e);
+    }
 
     Ok(())
 }
@@ -58,35 +85,56 @@ pub fn display(store: &Store) {
             .inventory_get(id)
             .expect("inventory id list out of sync");
         println!(" {id:06} | {:40} | ${:>9} | {qty:5}", item.name, item.cost);
+        if let Some(entity_ids) = store.inventory_entity_ids(id) {
+            let ids: Vec<String> = entity_ids.iter().map(|e| e.as_u32().to_string()).collect();
+            println!("          units in stock: {}", ids.join(", "));
+        }
     }
+    println!(
+        "{border}\n {} of {} SKUs stocked",
+        store.inventory_len(),
+        INVENTORY_CAPACITY
+    );
 }
 
-pub fn build_order(store: &mut Store) -> io::Result<Option<Vec<OrderLine>>> {
-    let mut order_qty: std::collections::HashMap<u32, u32> = std::collections::HashMap::new();
+pub fn build_order(store: &mut Store) -> io::Result<Option<Order>> {
+    let mut reserved: std::collections::HashMap<u32, (u32, Vec<ItemEntityId>)> =
+        std::collections::HashMap::new();
+    let mut txn = StoreTransaction::new(store);
 
     loop {
-        display(store);
-        let ids = store.inventory_ids_sorted();
+        display(txn.store());
+        let ids = txn.store().inventory_ids_sorted();
 
         let cmd = read_str("  > Select row # ('f' to finish, 'q' to quit): ")?;
         match cmd.as_str() {
             "f" => {
-                if order_qty.is_empty() {
+                if reserved.is_empty() {
                     eprintln!("Unable to complete order, no items have been added.");
                     continue;
                 }
 
-                let mut lines: Vec<OrderLine> = order_qty
+                let mut lines: Vec<OrderLine> = reserved
                     .into_iter()
-                    .map(|(item_id, qty)| OrderLine { item_id, qty })
+                    .map(|(item_id, (qty, entity_ids))| OrderLine {
+                        item_id,
+                        qty,
+                        entity_ids,
+                    })
                     .collect();
                 lines.sort_by_key(|l| l.item_id);
-                return Ok(Some(lines));
+
+                return match txn.commit(lines) {
+                    Ok(order) => Ok(Some(order)),
+                    Err(e) => {
+                        print_shop_error(&e);
+                        txn.rollback();
+                        Ok(None)
+                    }
+                };
             }
             "q" => {
-                for (item_id, qty) in order_qty.iter() {
-                    let _ = store.adjust_stock(*item_id, *qty as i32);
-                }
+                txn.rollback();
                 return Ok(None);
             }
             _ => {}
@@ -107,16 +155,81 @@ pub fn build_order(store: &mut Store) -> io::Result<Option<Vec<OrderLine>>> {
 
         let item_id = ids[row];
         let qty = retry_read_u32("  > Qty: ")?;
+        let (item, _avail) = txn
+            .store()
+            .inventory_get(item_id)
+            .expect("item id from sorted list must exist");
+
+        match item.kind {
+            ItemKind::Stackable => match txn.reserve_stock(item_id, qty) {
+                Ok(()) => {
+                    reserved.entry(item_id).or_insert((0, Vec::new())).0 += qty;
+                }
+                Err(e) => print_shop_error(&e),
+            },
+            ItemKind::Individual => match txn.reserve_entity_ids(item_id, qty) {
+                Ok(ids) => {
+                    let line = reserved.entry(item_id).or_insert((0, Vec::new()));
+                    line.0 += qty;
+                    line.1.extend(ids);
+                }
+                Err(e) => print_shop_error(&e),
+            },
+        }
+    }
+}
 
-        match store.adjust_stock(item_id, -(qty as i32)) {
-            Ok(_new_avail) => {
-                *order_qty.entry(item_id).or_insert(0) += qty;
-            }
-            Err(msg) => {
-                eprintln!("{msg}");
-            }
+/// Lists existing orders and prompts to move one to its next lifecycle
+/// status (ship/cancel/complete/return).
+pub fn manage_orders(store: &mut Store) -> io::Result<()> {
+    if store.orders().is_empty() {
+        println!("No orders yet.");
+        return Ok(());
+    }
+
+    println!("{:>6} | {:10} | {:>9} | ship", "ID#", "Status", "Total");
+    for o in store.orders() {
+        println!("{:>6} | {:10} | ${:>8} | {}", o.id, o.status.label(), o.cost, o.ship_weight);
+    }
+
+    let input = read_str("  > Order id (blank to go back): ")?;
+    if input.is_empty() {
+        return Ok(());
+    }
+    let order_id: u32 = match input.parse() {
+        Ok(n) => n,
+        Err(_) => {
+            eprintln!("Enter a numeric order id.");
+            return Ok(());
         }
+    };
+
+    let action = read_str("  > Action [ship/cancel/complete/return]: ")?;
+    let new_status = match action.as_str() {
+        "ship" => OrderStatus::Shipped {
+            tracking: read_str("  > Tracking #: ")?,
+        },
+        "cancel" => OrderStatus::Canceled {
+            reason: read_str("  > Reason: ")?,
+        },
+        "complete" => OrderStatus::Completed {
+            date_delivered: read_str("  > Delivery date: ")?,
+        },
+        "return" => OrderStatus::Returned {
+            reason: read_str("  > Reason: ")?,
+        },
+        _ => {
+            eprintln!("Unrecognized action.");
+            return Ok(());
+        }
+    };
+
+    match store.transition_order(order_id, new_status) {
+        Ok(order) => println!("order #{} is now {}", order.id, order.status.label()),
+        Err(e) => print_shop_error(&e),
     }
+
+    Ok(())
 }
 
 pub fn print_receipt(store: &Store, order: &Order) {
@@ -126,6 +239,10 @@ pub fn print_receipt(store: &Store, order: &Order) {
             .expect("Item is missing from inventory");
         let line_total = Cents::new(item.cost.as_u32() * l.qty);
         println!("  x{}  {}  ${}", l.qty, item.name, line_total);
+        if !l.entity_ids.is_empty() {
+            let ids: Vec<String> = l.entity_ids.iter().map(|e| e.as_u32().to_string()).collect();
+            println!("      units shipped: {}", ids.join(", "));
+        }
     }
     println!("total=${} ship={}", order.cost, order.ship_weight);
 }